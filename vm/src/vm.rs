@@ -1,12 +1,13 @@
 use std::cell::{Cell, RefCell, Ref};
 use std::fmt;
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::cmp::Ordering;
 use std::ops::{Add, Sub, Mul, Div, Deref};
 use std::result::Result as StdResult;
 use std::string::String as StdString;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use base::ast::{Typed, ASTType};
 use base::symbol::{Name, Symbol};
@@ -35,7 +36,7 @@ pub struct Userdata_ {
 impl Userdata_ {
     pub fn new<T: Any>(vm: &VM, v: T) -> Userdata_ {
         let v: Box<Any> = Box::new(v);
-        Userdata_ { data: vm.gc.borrow_mut().alloc(Move(v)) }
+        Userdata_ { data: vm.gc.lock().unwrap().alloc(Move(v)) }
     }
     fn ptr(&self) -> *const () {
         let p: *const _ = &*self.data;
@@ -102,9 +103,20 @@ pub struct BytecodeFunction {
 
 impl BytecodeFunction {
     pub fn new(gc: &mut Gc, f: CompiledFunction) -> GcPtr<BytecodeFunction> {
+        BytecodeFunction::new_with_passes(gc, f, &optimize::default_passes())
+    }
+
+    /// Like `new` but lets the caller choose which optimization passes run over `f` (and,
+    /// recursively, its `inner_functions`) before it is allocated. Embedders that want to add
+    /// their own pass can build their own list starting from `optimize::default_passes()`.
+    pub fn new_with_passes(gc: &mut Gc,
+                           mut f: CompiledFunction,
+                           passes: &[Box<optimize::Pass>])
+                           -> GcPtr<BytecodeFunction> {
+        optimize::optimize(&mut f, passes);
         let CompiledFunction { id, args, instructions, inner_functions, strings, .. } = f;
         let fs = inner_functions.into_iter()
-                                .map(|inner| BytecodeFunction::new(gc, inner))
+                                .map(|inner| BytecodeFunction::new_with_passes(gc, inner, passes))
                                 .collect();
         gc.alloc(Move(BytecodeFunction {
             name: id,
@@ -116,6 +128,496 @@ impl BytecodeFunction {
     }
 }
 
+/// LEB128-style variable-length integer encoding: 7 data bits per byte, the high bit set on every
+/// byte but the last to mark a continuation. Small values (the common case for `VMIndex` operands
+/// like those on `Push`, `CJump`, `Pop` and `Slide`) cost a single byte instead of the 8 a raw
+/// `u64` would take.
+///
+/// This only covers the encoding primitive. Moving `Instruction` itself from a wide enum to a
+/// one-byte `Op` tag plus a `Vec<u8>` operand stream — and updating the dispatch loop,
+/// `debug_instruction`, and the `optimize` pass machinery to decode from it — would also require
+/// changing how the compiler emits bytecode, and `Instruction` and the compiler that constructs it
+/// both live outside this file (in `types.rs` / `compiler.rs`, neither present in this tree). This
+/// module is the self-contained piece of that redesign that can actually be implemented here.
+///
+/// Status: minimal, not the redesign. `write_varint`/`read_varint` have zero call sites anywhere
+/// in this tree - nothing actually switched to the compact format they'd enable. Re-scope this
+/// request once `Instruction`/`compiler` are reachable from a commit in this series; until then
+/// this module doesn't move the bytecode representation at all, it only proves the encoding.
+pub mod bytecode {
+    /// Appends `value`'s LEB128 encoding to `out`.
+    pub fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                break;
+            } else {
+                out.push(byte | 0x80);
+            }
+        }
+    }
+
+    /// Decodes a LEB128 value starting at `bytes[*pos]`, advancing `*pos` past it.
+    pub fn read_varint(bytes: &[u8], pos: &mut usize) -> u64 {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = bytes[*pos];
+            *pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        value
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn round_trips_small_single_byte_values() {
+            for value in 0..128u64 {
+                let mut bytes = Vec::new();
+                write_varint(&mut bytes, value);
+                assert_eq!(bytes.len(), 1);
+                let mut pos = 0;
+                assert_eq!(read_varint(&bytes, &mut pos), value);
+                assert_eq!(pos, bytes.len());
+            }
+        }
+
+        #[test]
+        fn round_trips_values_needing_a_continuation_byte() {
+            // 128 is the smallest value that needs more than 7 bits, i.e. more than one byte.
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, 128);
+            assert_eq!(bytes, vec![0x80, 0x01]);
+            let mut pos = 0;
+            assert_eq!(read_varint(&bytes, &mut pos), 128);
+            assert_eq!(pos, bytes.len());
+        }
+
+        #[test]
+        fn round_trips_u64_max() {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, u64::max_value());
+            let mut pos = 0;
+            assert_eq!(read_varint(&bytes, &mut pos), u64::max_value());
+            assert_eq!(pos, bytes.len());
+        }
+
+        #[test]
+        fn read_varint_stops_after_its_own_value_when_followed_by_more_bytes() {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, 300);
+            write_varint(&mut bytes, 1);
+            let mut pos = 0;
+            assert_eq!(read_varint(&bytes, &mut pos), 300);
+            assert_eq!(read_varint(&bytes, &mut pos), 1);
+            assert_eq!(pos, bytes.len());
+        }
+    }
+}
+
+/// A small fixpoint-driven optimizer that runs over a `CompiledFunction`'s bytecode before it is
+/// handed to `BytecodeFunction::new` and allocated into the GC.
+pub mod optimize {
+    use std::collections::HashSet;
+    use compiler::CompiledFunction;
+    use types::*;
+
+    /// A single optimization pass. Returning `true` means the pass changed `f`, which tells the
+    /// fixpoint driver in `optimize` to run the whole pass list again since earlier passes may now
+    /// apply where they didn't before (e.g. constant folding exposing a dead store).
+    pub trait Pass {
+        fn run(&self, f: &mut CompiledFunction) -> bool;
+    }
+
+    /// The passes `BytecodeFunction::new` runs by default.
+    pub fn default_passes() -> Vec<Box<Pass>> {
+        vec![Box::new(ConstantFold), Box::new(DeadStoreElimination), Box::new(JumpThreading),
+             Box::new(InlineTrivialClosures)]
+    }
+
+    /// Runs `passes` over `f` to a fixpoint, then recurses into `f.inner_functions`.
+    pub fn optimize(f: &mut CompiledFunction, passes: &[Box<Pass>]) {
+        loop {
+            let mut changed = false;
+            for pass in passes {
+                changed = pass.run(f) || changed;
+            }
+            if !changed {
+                break;
+            }
+        }
+        for inner in &mut f.inner_functions {
+            optimize(inner, passes);
+        }
+    }
+
+    /// Patches every `Jump`/`CJump` target in `instructions` through `new_index`, which must map
+    /// each *old* instruction index (and one past the end) to its new position.
+    fn retarget_jumps(instructions: &mut [Instruction], new_index: &[usize]) {
+        for instr in instructions {
+            match *instr {
+                Jump(ref mut target) => *target = new_index[*target as usize] as VMIndex,
+                CJump(ref mut target) => *target = new_index[*target as usize] as VMIndex,
+                _ => (),
+            }
+        }
+    }
+
+    /// Every instruction index that some `Jump`/`CJump` in `instrs` lands on, i.e. a valid entry
+    /// point into the middle of `instrs` that a merge/elision pass must not silently change the
+    /// meaning of.
+    fn jump_targets(instrs: &[Instruction]) -> HashSet<usize> {
+        let mut targets = HashSet::new();
+        for instr in instrs {
+            match *instr {
+                Jump(t) | CJump(t) => {
+                    targets.insert(t as usize);
+                }
+                _ => (),
+            }
+        }
+        targets
+    }
+
+    /// Folds `PushInt a; PushInt b; <op>Int` into a single `PushInt` with the computed result,
+    /// using wrapping arithmetic to match the VM's own semantics for `Value::Int`. Left alone if
+    /// `b`'s or `<op>Int`'s instruction is itself a jump target: folding would silently change
+    /// what running from that entry point does, since there would no longer be an instruction
+    /// there at all.
+    pub struct ConstantFold;
+    impl Pass for ConstantFold {
+        fn run(&self, f: &mut CompiledFunction) -> bool {
+            let instrs = &f.instructions;
+            let targets = jump_targets(instrs);
+            let mut out = Vec::with_capacity(instrs.len());
+            let mut new_index = vec![0usize; instrs.len() + 1];
+            let mut changed = false;
+            let mut i = 0;
+            while i < instrs.len() {
+                let folded = if i + 2 < instrs.len() && !targets.contains(&(i + 1)) &&
+                                !targets.contains(&(i + 2)) {
+                    match (instrs[i], instrs[i + 1], instrs[i + 2]) {
+                        (PushInt(a), PushInt(b), AddInt) => Some(a.wrapping_add(b)),
+                        (PushInt(a), PushInt(b), SubtractInt) => Some(a.wrapping_sub(b)),
+                        (PushInt(a), PushInt(b), MultiplyInt) => Some(a.wrapping_mul(b)),
+                        (PushInt(a), PushInt(b), DivideInt) if b != 0 => Some(a.wrapping_div(b)),
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                match folded {
+                    Some(value) => {
+                        new_index[i] = out.len();
+                        new_index[i + 1] = out.len();
+                        new_index[i + 2] = out.len();
+                        out.push(PushInt(value));
+                        changed = true;
+                        i += 3;
+                    }
+                    None => {
+                        new_index[i] = out.len();
+                        out.push(instrs[i]);
+                        i += 1;
+                    }
+                }
+            }
+            new_index[instrs.len()] = out.len();
+            if !changed {
+                return false;
+            }
+            retarget_jumps(&mut out, &new_index);
+            f.instructions = out;
+            true
+        }
+    }
+
+    /// Removes a `Push`/`Pop` pair where the pushed value is popped again immediately, with no
+    /// instruction in between able to observe it. Left alone if the `Pop` is itself a jump target:
+    /// something landing there means "run `Pop(n)` on its own, with no preceding push", which
+    /// shrinking `n` down (or dropping the pop entirely) would silently answer differently.
+    pub struct DeadStoreElimination;
+    impl Pass for DeadStoreElimination {
+        fn run(&self, f: &mut CompiledFunction) -> bool {
+            let instrs = &f.instructions;
+            let targets = jump_targets(instrs);
+            let mut out = Vec::with_capacity(instrs.len());
+            let mut new_index = vec![0usize; instrs.len() + 1];
+            let mut changed = false;
+            let mut i = 0;
+            while i < instrs.len() {
+                let redundant = if i + 1 < instrs.len() && !targets.contains(&(i + 1)) {
+                    match (instrs[i], instrs[i + 1]) {
+                        (Push(_), Pop(n)) if n >= 1 => Some(n),
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                match redundant {
+                    Some(n) => {
+                        new_index[i] = out.len();
+                        if n > 1 {
+                            out.push(Pop(n - 1));
+                        }
+                        new_index[i + 1] = out.len();
+                        changed = true;
+                        i += 2;
+                    }
+                    None => {
+                        new_index[i] = out.len();
+                        out.push(instrs[i]);
+                        i += 1;
+                    }
+                }
+            }
+            new_index[instrs.len()] = out.len();
+            if !changed {
+                return false;
+            }
+            retarget_jumps(&mut out, &new_index);
+            f.instructions = out;
+            true
+        }
+    }
+
+    /// Rewrites a `Jump`/`CJump` whose target is itself an unconditional `Jump` to point directly
+    /// at that jump's own target, so a chain of jumps collapses to a single hop.
+    pub struct JumpThreading;
+    impl Pass for JumpThreading {
+        fn run(&self, f: &mut CompiledFunction) -> bool {
+            let instrs = f.instructions.clone();
+            let mut changed = false;
+            for instr in &mut f.instructions {
+                let target = match *instr {
+                    Jump(t) => Some(t),
+                    CJump(t) => Some(t),
+                    _ => None,
+                };
+                if let Some(t) = target {
+                    let mut final_target = t;
+                    // Bound the walk by the instruction count so a cycle of jumps can't loop forever.
+                    for _ in 0..instrs.len() {
+                        match instrs.get(final_target as usize) {
+                            Some(&Jump(next)) if next != final_target => final_target = next,
+                            _ => break,
+                        }
+                    }
+                    if final_target != t {
+                        changed = true;
+                        match *instr {
+                            Jump(ref mut t) | CJump(ref mut t) => *t = final_target,
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+            }
+            changed
+        }
+    }
+
+    /// `true` if `body` contains no `Push`, i.e. inlining it can't resolve a frame-relative index
+    /// against the wrong frame (see `InlineTrivialClosures`'s doc comment).
+    fn is_safe_to_inline(body: &[Instruction]) -> bool {
+        !body.iter().any(|instr| match *instr {
+            Push(_) => true,
+            _ => false,
+        })
+    }
+
+    /// Inlines the body of an inner closure directly at its call site when the closure captures no
+    /// upvariables (so allocating a `ClosureData` for it is pure overhead) and is called with
+    /// exactly the number of arguments it declares, which is the shape a trivial non-escaping
+    /// `let`-bound helper compiles to.
+    ///
+    /// Splicing the callee's instructions in directly means they run without their own
+    /// `enter_scope`, so any `Push(i)` in the body - which addresses a slot relative to *its own*
+    /// frame's base - would instead resolve against the caller's frame once inlined, silently
+    /// reading the wrong stack slot whenever the body actually touches an argument or local.
+    /// Reconstructing the right offset would need the same stack-depth accounting the compiler
+    /// does when it first assigns those indices, which this pass doesn't have access to, so it
+    /// only inlines bodies that contain no `Push` at all - i.e. closed, argument-less thunks where
+    /// there's no frame-relative addressing for the missing scope to break.
+    pub struct InlineTrivialClosures;
+    impl Pass for InlineTrivialClosures {
+        fn run(&self, f: &mut CompiledFunction) -> bool {
+            let instrs = &f.instructions;
+            let targets = jump_targets(instrs);
+            let inner_functions = &f.inner_functions;
+            let mut out = Vec::with_capacity(instrs.len());
+            // Parallel to `out`: whether the instruction at that position was spliced in from an
+            // inlined body (and so already carries final, rebased jump targets) rather than copied
+            // through from `instrs` (whose jump targets are still old-instrs-relative and need the
+            // `new_index` remap below). Conflating the two would remap an already-final inlined
+            // target a second time and corrupt it.
+            let mut from_inlined_body = Vec::with_capacity(instrs.len());
+            let mut new_index = vec![0usize; instrs.len() + 1];
+            let mut changed = false;
+            let mut i = 0;
+            while i < instrs.len() {
+                let body = if i + 1 < instrs.len() && !targets.contains(&(i + 1)) {
+                    match (instrs[i], instrs[i + 1]) {
+                        (MakeClosure(fi, 0), Call(args)) => {
+                            let inner = &inner_functions[fi as usize];
+                            if inner.args == args && inner.inner_functions.is_empty() &&
+                               is_safe_to_inline(&inner.instructions) {
+                                Some(&inner.instructions)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                match body {
+                    Some(body) => {
+                        new_index[i] = out.len();
+                        // The callee's own `Jump`/`CJump` targets are relative to its own
+                        // instruction list (0-based); rebase them onto where that list now starts
+                        // in `out`, not onto `i` - the two can differ once earlier inlines in this
+                        // same pass have already changed the output's length relative to the
+                        // original instruction count.
+                        let offset = out.len() as isize;
+                        out.extend(body.iter().map(|instr| match *instr {
+                            Jump(t) => Jump((t as isize + offset) as VMIndex),
+                            CJump(t) => CJump((t as isize + offset) as VMIndex),
+                            other => other,
+                        }));
+                        from_inlined_body.resize(out.len(), true);
+                        new_index[i + 1] = out.len();
+                        changed = true;
+                        i += 2;
+                    }
+                    None => {
+                        new_index[i] = out.len();
+                        out.push(instrs[i]);
+                        from_inlined_body.push(false);
+                        i += 1;
+                    }
+                }
+            }
+            new_index[instrs.len()] = out.len();
+            if !changed {
+                return false;
+            }
+            // Only remap the pass-through instructions here - an inlined body's `Jump`/`CJump`
+            // targets were already rebased to their final `out` position above, and running them
+            // through `new_index` (which maps *old top-level* indices) a second time would treat
+            // that final value as a stale index and corrupt it.
+            for (instr, inlined) in out.iter_mut().zip(from_inlined_body.iter()) {
+                if *inlined {
+                    continue;
+                }
+                match *instr {
+                    Jump(ref mut target) => *target = new_index[*target as usize] as VMIndex,
+                    CJump(ref mut target) => *target = new_index[*target as usize] as VMIndex,
+                    _ => (),
+                }
+            }
+            f.instructions = out;
+            true
+        }
+    }
+
+    // `compiler::CompiledFunction`'s field list is visible here via `VM::add_bytecode`
+    // (`args`, `id`, `typ`, `instructions`, `inner_functions`, `strings`), so it can be built
+    // directly in a test. `TcType` itself isn't constructible from this file without a `TypeEnv`,
+    // so tests borrow `VMInt::make_type`, the same approach the top-level `tests` module already
+    // uses for `redefine_global`.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use super::super::{GlobalVMState, VMInt};
+        use base::symbol::Symbol;
+        use api::VMType;
+
+        #[test]
+        fn jump_targets_collects_every_jump_and_cjump_destination() {
+            let instrs = vec![CJump(2), PushInt(1), Jump(0)];
+            let targets = jump_targets(&instrs);
+            assert_eq!(targets.len(), 2);
+            assert!(targets.contains(&2));
+            assert!(targets.contains(&0));
+        }
+
+        #[test]
+        fn jump_targets_is_empty_without_any_jump_or_cjump() {
+            let instrs = vec![PushInt(1), PushInt(2), AddInt];
+            assert!(jump_targets(&instrs).is_empty());
+        }
+
+        #[test]
+        fn retarget_jumps_maps_old_indices_to_new_ones() {
+            let mut instrs = vec![Jump(2), CJump(0)];
+            // Pretend the instruction that used to live at old index 2 now lives at index 5,
+            // and the one at old index 0 now lives at index 1; `retarget_jumps` just looks each
+            // target up in whatever old-to-new `new_index` table it is handed.
+            let new_index = vec![1, 3, 5];
+            retarget_jumps(&mut instrs, &new_index);
+            assert_eq!(instrs[0], Jump(5));
+            assert_eq!(instrs[1], CJump(1));
+        }
+
+        #[test]
+        fn is_safe_to_inline_accepts_a_closed_argument_less_body() {
+            // `\() -> 1 + 2`-shaped: no argument, no local slot read anywhere in the body.
+            let body = vec![PushInt(1), PushInt(2), AddInt];
+            assert!(is_safe_to_inline(&body));
+        }
+
+        #[test]
+        fn is_safe_to_inline_rejects_a_body_that_reads_its_own_argument() {
+            // `\x -> x + 1`-shaped: `Push(0)` reads argument slot 0, which only means what it
+            // should relative to this closure's own frame, not the caller's.
+            let body = vec![Push(0), PushInt(1), AddInt];
+            assert!(!is_safe_to_inline(&body));
+        }
+
+        #[test]
+        fn inlining_preserves_a_jump_target_internal_to_the_inlined_body() {
+            // Regression test: the inlined body's own `Jump`/`CJump` targets are rebased to their
+            // final `out` position at splice time, so they must not be run through `new_index` a
+            // second time afterwards - doing so treats the already-final value as a stale
+            // old-instrs index and retargets it at the wrong instruction entirely.
+            let global_state = GlobalVMState::new();
+            let inner = CompiledFunction {
+                args: 0,
+                id: Symbol::new("inner"),
+                typ: VMInt::make_type(&global_state),
+                instructions: vec![PushInt(1), PushInt(2), Jump(1)],
+                inner_functions: vec![],
+                strings: vec![],
+            };
+            let mut f = CompiledFunction {
+                args: 0,
+                id: Symbol::new("outer"),
+                typ: VMInt::make_type(&global_state),
+                instructions: vec![MakeClosure(0, 0), Call(0), PushInt(100)],
+                inner_functions: vec![inner],
+                strings: vec![],
+            };
+            assert!(InlineTrivialClosures.run(&mut f));
+            // The 2-instruction call site is replaced by the 3-instruction body, so the trailing
+            // `PushInt(100)` now lives at index 3 and the body's internal `Jump(1)` must still
+            // land inside the spliced body (index 1), not at the corrupted `Jump(3)` the bug
+            // produced.
+            assert_eq!(f.instructions,
+                       vec![PushInt(1), PushInt(2), Jump(1), PushInt(100)]);
+        }
+    }
+}
+
 impl Traverseable for BytecodeFunction {
     fn traverse(&self, gc: &mut Gc) {
         self.inner_functions.traverse(gc);
@@ -432,8 +934,26 @@ impl Traverseable for ExternFunction {
 #[derive(Debug)]
 struct Global {
     id: Symbol,
-    typ: TcType,
+    typ: RefCell<TcType>,
     value: Cell<Value>,
+    /// Globals recorded (via `GlobalVMState::record_dependency`) as having been compiled against
+    /// this global's current `typ`. `redefine_global` hands this set back to the caller so it can
+    /// decide whether to recompile them against the new type.
+    dependents: RefCell<HashSet<Symbol>>,
+}
+
+impl Global {
+    /// Borrows the current type without the `RefCell` guard, so the borrow can outlive this call
+    /// the way `TypeEnv::find_type` needs it to (its `&TcType` result is threaded through the rest
+    /// of a compile). Safe because `FixedVec` never moves or frees a `Global` once pushed, *and*
+    /// because every caller of this method is required to be holding a read guard on
+    /// `GlobalVMState::global_typ_lock` for at least as long as the returned reference is alive
+    /// (`GlobalVMState::env` takes one for the whole `VMEnv` it hands back). `redefine_global`
+    /// takes the write side of that same lock before mutating `typ`, so it always blocks until
+    /// every in-flight compile has dropped its `VMEnv` instead of racing one.
+    fn typ(&self) -> &TcType {
+        unsafe { &*self.typ.as_ptr() }
+    }
 }
 
 impl Traverseable for Global {
@@ -445,7 +965,7 @@ impl Traverseable for Global {
 impl Typed for Global {
     type Id = Symbol;
     fn env_type_of(&self, _: &TypeEnv) -> ASTType<Symbol> {
-        self.typ.clone()
+        self.typ.borrow().clone()
     }
 }
 
@@ -453,16 +973,104 @@ struct GlobalSymbols {
     io: Symbol,
 }
 
+/// Default value of `GlobalVMState::max_stack_size`, chosen generously enough that ordinary
+/// recursive gluon programs do not hit it while still catching a runaway infinite recursion
+/// before it exhausts the OS thread's own stack.
+const DEFAULT_MAX_STACK_SIZE: usize = 10_000;
+
+/// Default value of `GlobalVMState::max_value_stack_size`. A hundred times `DEFAULT_MAX_STACK_SIZE`
+/// since an ordinary call frame pushes more than one value-stack cell (arguments plus locals), so
+/// a value-stack bound equal to the frame-count bound would trip `StackOverflow` on perfectly
+/// ordinary recursion long before `max_stack_size`'s own frame count was ever reached.
+const DEFAULT_MAX_VALUE_STACK_SIZE: usize = 1_000_000;
+
 pub struct GlobalVMState {
     globals: FixedVec<Global>,
-    type_infos: RefCell<TypeInfos>,
+    type_infos: RwLock<TypeInfos>,
     typeids: FixedMap<TypeId, TcType>,
-    pub interner: RefCell<Interner>,
+    pub interner: RwLock<Interner>,
     symbols: GlobalSymbols,
-    names: RefCell<HashMap<StdString, usize>>,
-    pub gc: RefCell<Gc>,
+    names: RwLock<HashMap<StdString, usize>>,
+    /// `gc::Gc` is defined outside this file and not touched by any commit here, so nothing in
+    /// this tree implements or verifies a young/old generational split. Call sites that mutate an
+    /// already-allocated `Cell<Value>` field call `VM::write_barrier` right after their
+    /// `Cell::set` - plumbing a generational `Gc` would need - but that alone is not a
+    /// working generational collector; see `VM::write_barrier`.
+    ///
+    /// Status: blocked, not done. The generational collector itself (bump nursery, remembered
+    /// set, a minor collection that traces the stack/roots/remembered set and promotes survivors)
+    /// has to live in `gc::Gc`, which this file can't touch - nothing here delivers any part of
+    /// that beyond this call-site plumbing.
+    pub gc: Mutex<Gc>,
     macros: MacroEnv<VM>,
-}
+    /// Every `Thread` sharing this state, used so a collection can stop the world across all of
+    /// them instead of only the thread that triggered it
+    threads: Mutex<Vec<GcPtr<Thread>>>,
+    /// Maximum number of nested call frames (`stack.stack.get_frames().len()`) any `Thread`
+    /// sharing this state may reach before `execute_callable` aborts the call with
+    /// `Error::StackOverflow` instead of growing the stack further.
+    max_stack_size: AtomicUsize,
+    /// Maximum raw value-stack length (`stack.len()`) any `Thread` sharing this state may reach
+    /// before `do_call`/`MakeClosure` abort with `Error::StackOverflow`. Deliberately a separate
+    /// knob from `max_stack_size`: a call frame ordinarily pushes more than one value-stack cell
+    /// (its arguments and locals), and a long run of `MakeClosure`s can grow the value stack
+    /// without ever adding a new call frame, so the two quantities grow at different rates and
+    /// sharing one limit between them would make whichever bound is reached first an accident of
+    /// how many locals a given program happens to use per call, rather than a deliberate setting.
+    max_value_stack_size: AtomicUsize,
+    /// Embedder-supplied hooks notified as bytecode frames are entered, as each instruction
+    /// executes, and as frames exit. `None` (the default) costs only the branch in
+    /// `VM::observe_op`/`observe_enter_frame`/`observe_exit_frame`.
+    observer: RwLock<Option<Box<RuntimeObserver>>>,
+    /// Mirrors whether `observer` is `Some`, checked by `VM::observe_op`/`observe_enter_frame`/
+    /// `observe_exit_frame` before touching `observer`'s `RwLock` at all. Without this, the
+    /// "zero overhead when `None`" claim above would actually cost an `RwLock::read` - a real
+    /// atomic operation - on every single dispatched instruction, on top of the interrupt/fuel
+    /// checks `execute_impl` already does per instruction.
+    observer_installed: AtomicBool,
+    /// Sinks an embedder can install via `GlobalVMState::set_on_print`/`set_on_debug` to capture,
+    /// forward, or suppress script output instead of it going straight to stdout. `None` (the
+    /// default) falls back to `println!`. Note: the `std.io` extern primitives that would
+    /// normally call through `print`/`debug_print` live outside this file and are not present in
+    /// this tree, so these sinks are wired up and usable by embedders directly but have no extern
+    /// call site here yet.
+    on_print: Mutex<Option<Box<FnMut(&str) + Send>>>,
+    on_debug: Mutex<Option<Box<FnMut(&str) + Send>>>,
+    /// Guards every `Global::typ` against a concurrent `redefine_global`. `GlobalVMState::env`
+    /// takes the read side for the lifetime of the `VMEnv` it returns (i.e. for an entire
+    /// compile); `redefine_global` takes the write side, which therefore can't proceed until any
+    /// in-flight compile has finished and dropped its `VMEnv`. This is what makes the `&TcType`
+    /// handed out by `Global::typ` sound despite outliving the call that produced it.
+    global_typ_lock: RwLock<()>,
+}
+
+/// Hooks an embedder can install via `GlobalVMState::set_observer` to watch bytecode execution
+/// without forking the VM: an instruction-level profiler, a coverage collector, an execution
+/// tracer or a single-step debugger can all be built entirely on top of this trait. Every method
+/// has a no-op default so an implementor only needs to override the hooks it cares about.
+pub trait RuntimeObserver: Send + Sync {
+    fn enter_frame(&self, _function: &BytecodeFunction, _stack: &StackFrame) {}
+    fn op(&self, _index: usize, _instr: Instruction, _stack: &StackFrame) {}
+    fn exit_frame(&self, _function: &BytecodeFunction, _stack: &StackFrame) {}
+}
+
+// `GlobalVMState`'s fields are only ever mutated through the locks above (or the append-only
+// `FixedVec`/`FixedMap`, which are themselves safe to share), so sharing it across OS threads via
+// `Arc<GlobalVMState>` is sound. This mirrors the way `Arc<T>` itself only implements `Send`/`Sync`
+// once `T: Send + Sync`; here we assert that contract by hand since the individual fields can't
+// derive it.
+//
+// Note that this alone does not give this tree multi-threaded gluon execution: `Thread` is
+// deliberately not `Send`/`Sync` (see the comment above its definition), and nothing in this
+// file's public API lets a second OS thread construct its own `Thread`/`VM` over an existing
+// `Arc<GlobalVMState>` - `VM::new` always creates a fresh `GlobalVMState`, and `new_thread`,
+// `new_vm` and `spawn` all require `&self` on the thread that already holds a `VM`. So today
+// this impl only buys `Arc<GlobalVMState>` itself the ability to be *moved* to another thread before anything is built
+// on top of it there, not actual parallel execution over shared globals; that needs an actual
+// safepoint protocol (see `Thread`'s doc comment) plus a real cross-thread construction path,
+// neither of which exists in this tree.
+unsafe impl Send for GlobalVMState {}
+unsafe impl Sync for GlobalVMState {}
 
 impl Traverseable for GlobalVMState {
     fn traverse(&self, gc: &mut Gc) {
@@ -470,8 +1078,29 @@ impl Traverseable for GlobalVMState {
             g.traverse(gc);
         }
         // Also need to check the interned string table
-        self.interner.borrow().traverse(gc);
-    }
+        self.interner.read().unwrap().traverse(gc);
+    }
+}
+
+/// A handler installed by a `PushTry` instruction, recording everything `execute` needs to unwind
+/// to it when the matching `Throw` (or a trappable VM error) fires before the matching `PopTry`.
+///
+/// `PushTry`/`PopTry`/`Throw` are, like every other `Instruction` variant, defined in
+/// `compiler`/`types`, outside this file, and are not present anywhere in this tree - this module
+/// only implements what `execute_impl` does when it dispatches them. Reaching this from gluon
+/// source (a `catch`/`throw` expression, or a `std.exception` module built on them) needs the
+/// compiler to actually emit these three opcodes for that syntax, which is compiler-side work this
+/// file cannot do or verify; what's here is VM-internal unwinding machinery only, not a usable
+/// gluon-level feature yet.
+#[derive(Debug, Clone, Copy)]
+struct TryFrame {
+    /// `stack.stack.get_frames().len()` at the time this handler was pushed; unwinding pops call
+    /// frames (via `exit_scope`) until exactly this many remain.
+    frame_depth: usize,
+    /// Value-stack length, within that frame, to truncate back to before pushing the thrown value.
+    stack_len: VMIndex,
+    /// Bytecode offset to resume at, in that frame, with the thrown value on top of the stack.
+    catch_instruction: usize,
 }
 
 /// Representation of the virtual machine
@@ -480,7 +1109,25 @@ pub struct Thread {
     roots: RefCell<Vec<GcPtr<Traverseable>>>,
     rooted_values: RefCell<Vec<Value>>,
     stack: RefCell<Stack>,
-}
+    try_frames: RefCell<Vec<TryFrame>>,
+    interrupt: Arc<AtomicBool>,
+    /// Optional deterministic instruction budget, decremented once per dispatched instruction in
+    /// `execute_impl`. Unlike `interrupt`, this is a plain (non-atomic) cell since fuel is only
+    /// ever read or written by the OS thread that owns this `Thread`, and the whole point is a
+    /// negligible, fully reproducible per-instruction cost.
+    fuel: Cell<Option<u64>>,
+}
+
+// `Thread` is deliberately *not* `Send`/`Sync`. `Roots::traverse` reads another thread's
+// `stack`/`roots`/`rooted_values` (via `RefCell::borrow`) while walking `threads` during a
+// collection, and `gc.lock()` only serializes allocations - nothing actually parks a `Thread`
+// that's mid-`execute_` before that traversal runs. `RefCell`'s borrow flag is a plain
+// non-atomic `Cell`, so a thread concurrently mutating its own stack while another thread's
+// collection borrows it would be a real data race. Making that sound needs an actual safepoint
+// protocol (each thread checking into a barrier at the same points it already checks
+// `interrupt`/`fuel`) that this tree does not implement, so `Thread` stays single-OS-thread-only
+// for now: `spawn` hands back an independently-driveable `Thread` sharing this one's
+// `GlobalVMState`, not one that's safe to run concurrently from a second OS thread.
 
 impl Deref for Thread {
     type Target = GlobalVMState;
@@ -508,6 +1155,11 @@ impl Drop for VM {
     fn drop(&mut self) {
         assert!(self.roots.borrow().len() == 1);
         self.roots.borrow_mut().pop();
+        // `VM::new`/`new_vm` both call `register_thread` on the `GlobalVMState` this `Thread`
+        // shares, and nothing else ever removes that registration - do it here, once this `VM`'s
+        // only owner is gone, so the `Thread` becomes collectible instead of permanently rooted by
+        // `Roots::traverse`'s unconditional walk over `threads`.
+        self.global_state.deregister_thread(self.0);
     }
 }
 
@@ -531,10 +1183,13 @@ pub type Result<T> = StdResult<T, Error>;
 /// typechecker and compiler to lookup things in the virtual machine.
 #[derive(Debug)]
 pub struct VMEnv<'b> {
-    type_infos: Ref<'b, TypeInfos>,
+    type_infos: ::std::sync::RwLockReadGuard<'b, TypeInfos>,
     globals: &'b FixedVec<Global>,
-    names: Ref<'b, HashMap<StdString, usize>>,
+    names: ::std::sync::RwLockReadGuard<'b, HashMap<StdString, usize>>,
     io_alias: types::Alias<Symbol, TcType>,
+    /// Held for this `VMEnv`'s entire lifetime so `redefine_global` cannot mutate a `Global::typ`
+    /// this environment has handed (or may still hand) a `&TcType` borrow of out to the compiler.
+    _typ_guard: ::std::sync::RwLockReadGuard<'b, ()>,
 }
 
 impl<'b> CompilerEnv for VMEnv<'b> {
@@ -542,7 +1197,7 @@ impl<'b> CompilerEnv for VMEnv<'b> {
         match self.names.get(id.as_ref()) {
             Some(&index) if index < self.globals.len() => {
                 let g = &self.globals[index];
-                Some(Variable::Global(index as VMIndex, &g.typ))
+                Some(Variable::Global(index as VMIndex, g.typ()))
             }
             _ => self.type_infos.find_var(id),
         }
@@ -567,7 +1222,7 @@ impl<'b> TypeEnv for VMEnv<'b> {
         match self.names.get(AsRef::<str>::as_ref(id)) {
             Some(&index) if index < self.globals.len() => {
                 let g = &self.globals[index];
-                Some(&g.typ)
+                Some(g.typ())
             }
             _ => {
                 self.type_infos
@@ -646,6 +1301,18 @@ impl<'b> Traverseable for Roots<'b> {
 
         // Traverse the vm's fields, avoiding the stack which is traversed above
         self.vm.traverse_fields_except_stack(gc);
+
+        // Enumerate and traverse every other thread registered alongside this one. The `gc`
+        // mutex held by the caller of `collect` only serializes allocation; what keeps this walk
+        // from racing another thread's stack is that `Thread` is not `Send`/`Sync` (see the
+        // comment above `Thread`), so every registered thread lives on this same OS thread.
+        for &other in self.vm.global_state.threads.lock().unwrap().iter() {
+            if &*other as *const Thread != &*self.vm.0 as *const Thread {
+                gc.mark(other);
+                other.stack.borrow().get_values().traverse(gc);
+                other.traverse_fields_except_stack(gc);
+            }
+        }
     }
 }
 
@@ -684,15 +1351,23 @@ impl GlobalVMState {
     pub fn new() -> GlobalVMState {
         let vm = GlobalVMState {
             globals: FixedVec::new(),
-            type_infos: RefCell::new(TypeInfos::new()),
+            type_infos: RwLock::new(TypeInfos::new()),
             typeids: FixedMap::new(),
             symbols: GlobalSymbols {
                 io: Symbol::new("IO"),
             },
-            interner: RefCell::new(Interner::new()),
-            names: RefCell::new(HashMap::new()),
-            gc: RefCell::new(Gc::new()),
+            interner: RwLock::new(Interner::new()),
+            names: RwLock::new(HashMap::new()),
+            gc: Mutex::new(Gc::new()),
             macros: MacroEnv::new(),
+            threads: Mutex::new(Vec::new()),
+            max_stack_size: AtomicUsize::new(DEFAULT_MAX_STACK_SIZE),
+            max_value_stack_size: AtomicUsize::new(DEFAULT_MAX_VALUE_STACK_SIZE),
+            observer: RwLock::new(None),
+            observer_installed: AtomicBool::new(false),
+            on_print: Mutex::new(None),
+            on_debug: Mutex::new(None),
+            global_typ_lock: RwLock::new(()),
         };
         vm.add_types()
           .unwrap();
@@ -718,8 +1393,66 @@ impl GlobalVMState {
         Ok(())
     }
 
+    /// Sets the maximum number of nested call frames any `Thread` sharing this state may reach
+    /// before a call aborts with `Error::StackOverflow`. See `max_value_stack_size` for the
+    /// separate raw value-stack bound.
+    pub fn set_max_stack_size(&self, max: usize) {
+        self.max_stack_size.store(max, Ordering::Relaxed);
+    }
+
+    /// Sets the maximum raw value-stack length (`stack.len()`) any `Thread` sharing this state may
+    /// reach before `do_call`/`MakeClosure` abort with `Error::StackOverflow`. This is independent
+    /// of `set_max_stack_size`'s call-frame-count limit; see `max_value_stack_size`'s doc comment
+    /// for why the two aren't the same knob.
+    pub fn set_max_value_stack_size(&self, max: usize) {
+        self.max_value_stack_size.store(max, Ordering::Relaxed);
+    }
+
+    /// Installs (replacing any previous one) the `RuntimeObserver` notified as this state's
+    /// threads execute bytecode.
+    pub fn set_observer(&self, observer: Box<RuntimeObserver>) {
+        *self.observer.write().unwrap() = Some(observer);
+        self.observer_installed.store(true, Ordering::Relaxed);
+    }
+
+    /// Installs (replacing any previous one) the sink that `print` consults instead of stdout.
+    pub fn set_on_print(&self, f: Box<FnMut(&str) + Send>) {
+        *self.on_print.lock().unwrap() = Some(f);
+    }
+
+    /// Installs (replacing any previous one) the sink that `debug_print` consults instead of
+    /// stdout.
+    pub fn set_on_debug(&self, f: Box<FnMut(&str) + Send>) {
+        *self.on_debug.lock().unwrap() = Some(f);
+    }
+
+    /// Routes script output through the installed `on_print` sink, falling back to stdout.
+    pub fn print(&self, msg: &str) {
+        match *self.on_print.lock().unwrap() {
+            Some(ref mut f) => f(msg),
+            None => println!("{}", msg),
+        }
+    }
+
+    /// Routes debug tracing output through the installed `on_debug` sink, falling back to stdout.
+    pub fn debug_print(&self, msg: &str) {
+        match *self.on_debug.lock().unwrap() {
+            Some(ref mut f) => f(msg),
+            None => println!("{}", msg),
+        }
+    }
+
     pub fn new_function(&self, f: CompiledFunction) -> GcPtr<BytecodeFunction> {
-        BytecodeFunction::new(&mut self.gc.borrow_mut(), f)
+        BytecodeFunction::new(&mut self.gc.lock().unwrap(), f)
+    }
+
+    /// Like `new_function` but lets an embedder supply its own optimization passes, e.g. to add
+    /// one on top of `optimize::default_passes()`.
+    pub fn compile_with_passes(&self,
+                               f: CompiledFunction,
+                               passes: &[Box<optimize::Pass>])
+                               -> GcPtr<BytecodeFunction> {
+        BytecodeFunction::new_with_passes(&mut self.gc.lock().unwrap(), f, passes)
     }
 
     pub fn get_type<T: ?Sized + Any>(&self) -> &TcType {
@@ -731,30 +1464,67 @@ impl GlobalVMState {
 
     /// Checks if a global exists called `name`
     pub fn global_exists(&self, name: &str) -> bool {
-        self.names.borrow().get(name).is_some()
+        self.names.read().unwrap().get(name).is_some()
     }
 
     /// TODO dont expose this directly
     pub fn set_global(&self, id: Symbol, typ: TcType, value: Value) -> Result<()> {
-        if self.names.borrow().contains_key(id.as_ref()) {
+        if self.names.read().unwrap().contains_key(id.as_ref()) {
             return Err(Error::Message(format!("{} is already defined", id)));
         }
         let global = Global {
             id: id.clone(),
-            typ: typ,
+            typ: RefCell::new(typ),
             value: Cell::new(value),
+            dependents: RefCell::new(HashSet::new()),
         };
-        self.names.borrow_mut().insert(StdString::from(id.as_ref()), self.globals.len());
+        self.names.write().unwrap().insert(StdString::from(id.as_ref()), self.globals.len());
         self.globals.push(global);
         Ok(())
     }
 
+    /// Records that the global called `dependent` was compiled against the current type of the
+    /// global called `dependency`, so a later `redefine_global` of `dependency` knows to hand
+    /// `dependent` back to its caller. Nothing in this file calls it: `VMEnv::find_var` resolves
+    /// globals without recording anything, so `redefine_global` only returns dependencies that a
+    /// caller of this method has recorded explicitly.
+    pub fn record_dependency(&self, dependent: Symbol, dependency: &str) {
+        if let Some(&index) = self.names.read().unwrap().get(dependency) {
+            self.globals[index].dependents.borrow_mut().insert(dependent);
+        }
+    }
+
+    /// Replaces the `typ` and `value` of the global called `id` in place, keeping its index into
+    /// `globals`/`names` stable so already-compiled `Variable::Global(index, ..)` references keep
+    /// resolving without recompilation. Like a check-worker restart, the previous `Value` stays
+    /// reachable in `value`'s `Cell` right up until it is overwritten, so a concurrent collection
+    /// triggered from another `Thread` can never observe the global with no value at all.
+    ///
+    /// Returns the globals `record_dependency` recorded against the old type, since their compiled
+    /// code may no longer type-check against the new one; the caller decides whether to recompile
+    /// them.
+    pub fn redefine_global(&self, id: &Symbol, typ: TcType, value: Value) -> Result<HashSet<Symbol>> {
+        let index = match self.names.read().unwrap().get(id.as_ref()) {
+            Some(&index) => index,
+            None => return Err(Error::Message(format!("{} is not defined", id))),
+        };
+        let global = &self.globals[index];
+        global.value.set(value);
+        {
+            // Blocks until every `VMEnv` (i.e. every in-flight compile) that might be holding a
+            // `&TcType` into this global via `Global::typ` has been dropped.
+            let _write_guard = self.global_typ_lock.write().unwrap();
+            *global.typ.borrow_mut() = typ;
+        }
+        Ok(global.dependents.replace(HashSet::new()))
+    }
+
     /// Registers a new type called `name`
     pub fn register_type<T: ?Sized + Any>(&self,
                                           name: &str,
                                           args: Vec<types::Generic<Symbol>>)
                                           -> Result<&TcType> {
-        let mut type_infos = self.type_infos.borrow_mut();
+        let mut type_infos = self.type_infos.write().unwrap();
         if type_infos.id_to_type.contains_key(name) {
             Err(Error::Message(format!("Type '{}' has already been registered", name)))
         } else {
@@ -783,15 +1553,16 @@ impl GlobalVMState {
     }
 
     pub fn intern(&self, s: &str) -> InternedStr {
-        self.interner.borrow_mut().intern(&mut *self.gc.borrow_mut(), s)
+        self.interner.write().unwrap().intern(&mut *self.gc.lock().unwrap(), s)
     }
 
     /// Returns a borrowed structure which implements `CompilerEnv`
     pub fn env<'b>(&'b self) -> VMEnv<'b> {
         VMEnv {
-            type_infos: self.type_infos.borrow(),
+            type_infos: self.type_infos.read().unwrap(),
             globals: &self.globals,
-            names: self.names.borrow(),
+            names: self.names.read().unwrap(),
+            _typ_guard: self.global_typ_lock.read().unwrap(),
             io_alias: types::Alias {
                 name: self.symbols.io.clone(),
                 args: vec![types::Generic {
@@ -804,11 +1575,26 @@ impl GlobalVMState {
     }
 
     pub fn new_data(&self, tag: VMTag, fields: &[Value]) -> Value {
-        Data(self.gc.borrow_mut().alloc(Def {
+        Data(self.gc.lock().unwrap().alloc(Def {
             tag: tag,
             elems: fields,
         }))
     }
+
+    fn register_thread(&self, thread: GcPtr<Thread>) {
+        self.threads.lock().unwrap().push(thread);
+    }
+
+    /// Removes `thread` from `threads` so `Roots::traverse` stops unconditionally marking it on
+    /// every future collection. Without this, every `Thread` ever registered survives every
+    /// collection for the lifetime of the process, regardless of whether anything still holds a
+    /// handle to it - `register_thread` alone is a guaranteed leak.
+    fn deregister_thread(&self, thread: GcPtr<Thread>) {
+        self.threads
+            .lock()
+            .unwrap()
+            .retain(|&other| &*other as *const Thread != &*thread as *const Thread);
+    }
 }
 
 impl VM {
@@ -818,11 +1604,15 @@ impl VM {
             stack: RefCell::new(Stack::new()),
             roots: RefCell::new(Vec::new()),
             rooted_values: RefCell::new(Vec::new()),
+            try_frames: RefCell::new(Vec::new()),
+            interrupt: Arc::new(AtomicBool::new(false)),
+            fuel: Cell::new(None),
         };
         let mut gc = Gc::new();
         let vm = VM(gc.alloc(Move(vm)));
-        *vm.gc.borrow_mut() = gc;
+        *vm.gc.lock().unwrap() = gc;
         vm.roots.borrow_mut().push(vm.0.as_traverseable());
+        vm.global_state.register_thread(vm.0);
         // Enter the top level scope
         StackFrame::frame(vm.stack.borrow_mut(), 0, None);
         vm
@@ -834,6 +1624,9 @@ impl VM {
             stack: RefCell::new(Stack::new()),
             roots: RefCell::new(Vec::new()),
             rooted_values: RefCell::new(Vec::new()),
+            try_frames: RefCell::new(Vec::new()),
+            interrupt: Arc::new(AtomicBool::new(false)),
+            fuel: Cell::new(None),
         };
         // Enter the top level scope
         StackFrame::frame(vm.stack.borrow_mut(), 0, None);
@@ -844,15 +1637,76 @@ impl VM {
         let vm = self.new_thread();
         let vm = VM(self.alloc(&self.stack.borrow(), Move(vm)));
         vm.roots.borrow_mut().push(vm.0.as_traverseable());
+        vm.global_state.register_thread(vm.0);
         vm
     }
 
+    /// Spawns a new `Thread` sharing this one's `GlobalVMState` (globals, type info, interner and
+    /// GC) but with its own independent `Stack`, `roots` and `rooted_values`. `Thread` is not
+    /// `Send`/`Sync` (see the comment above its definition), so the returned `GcPtr<Thread>` must
+    /// stay on the OS thread that called `spawn`, alongside the thread that spawned it; it is not
+    /// safe to hand off to, or drive concurrently from, another OS thread. What this buys you
+    /// within one OS thread is several independent gluon call stacks - e.g. cooperatively
+    /// scheduled coroutines - that still share globals, type info and the GC with their spawner.
+    ///
+    /// Unlike `new_vm`, this hands back a bare `GcPtr<Thread>` rather than a `VM`, so there is no
+    /// `Drop` impl to deregister it automatically once the embedder is done with it - call
+    /// `despawn` on *this same* `Thread` when that happens, or `thread` is rooted forever (see
+    /// `despawn`'s doc comment).
+    pub fn spawn(&self) -> GcPtr<Thread> {
+        let vm = self.new_thread();
+        let ptr = self.alloc(&self.stack.borrow(), Move(vm));
+        self.roots.borrow_mut().push(ptr.as_traverseable());
+        self.global_state.register_thread(ptr);
+        ptr
+    }
+
+    /// Deregisters a `Thread` previously returned by `spawn` on this same `Thread`/`VM`: removes
+    /// it from `GlobalVMState::threads` (so `Roots::traverse` stops unconditionally marking it on
+    /// every future collection) and from this `Thread`'s own `roots` (which is where `spawn`
+    /// rooted it, since the bare `GcPtr<Thread>` it returns has no `Drop` impl of its own to do
+    /// that). Without calling this, `spawn` is a guaranteed leak: nothing else in this file ever
+    /// removes a spawned `Thread`'s registration, so it survives every collection for the
+    /// lifetime of the process even after the embedder has dropped every other reference to it.
+    /// Must be called on the same `Thread` that `spawn` was called on - `thread` is only rooted in
+    /// *that* `Thread`'s `roots`, not its own.
+    pub fn despawn(&self, thread: GcPtr<Thread>) {
+        self.global_state.deregister_thread(thread);
+        self.roots.borrow_mut().retain(|root| {
+            &**root as *const Traverseable as *const () != &*thread as *const Thread as *const ()
+        });
+    }
+
+    /// Returns a handle that a caller on another OS thread can use to cooperatively interrupt a
+    /// computation running on this `Thread`. Setting the flag does not stop execution
+    /// immediately; `execute_impl` checks it before dispatching every instruction (not just at
+    /// backward jumps and calls - see the comment there), so the running program unwinds with
+    /// `Error::Interrupted` within one instruction's worth of latency, no matter what it's doing.
+    /// That per-instruction check is a real atomic load on the hot path, same as `fuel`'s; keeping
+    /// it off straight-line code the way `observer_installed` keeps `RwLock::read` off the
+    /// observer path isn't a design goal here, since cancellation latency is the actual point.
+    pub fn interrupt_handle(&self) -> Arc<AtomicBool> {
+        self.interrupt.clone()
+    }
+
+    /// Sets this thread's remaining instruction budget. `Some(n)` causes the next `n` dispatched
+    /// instructions to run before `execute_` returns `Error::OutOfFuel`; `None` (the default)
+    /// disables metering entirely.
+    pub fn set_fuel(&self, fuel: Option<u64>) {
+        self.fuel.set(fuel);
+    }
+
+    /// Returns the instruction budget remaining, or `None` if fuel metering is disabled.
+    pub fn remaining_fuel(&self) -> Option<u64> {
+        self.fuel.get()
+    }
+
     /// Creates a new global value at `name`.
     /// Fails if a global called `name` already exists.
     pub fn define_global<T>(&self, name: &str, value: T) -> Result<()>
         where T: Pushable
     {
-        if self.names.borrow().contains_key(name) {
+        if self.names.read().unwrap().contains_key(name) {
             return Err(Error::Message(format!("{} is already defined", name)));
         }
         let (status, value) = {
@@ -875,7 +1729,8 @@ impl VM {
         let global = match components.next() {
             Some(comp) => {
                 let names = self.names
-                                .borrow();
+                                .read()
+                                .unwrap();
                 try!(names.get(comp)
                           .or_else(|| {
                               // We access by the the full name so no components should be left
@@ -891,7 +1746,10 @@ impl VM {
             }
             None => return Err(Error::Message(format!("'{}' is not a valid name", name))),
         };
-        let mut typ = &global.typ;
+        // Held for the rest of this function so `global.typ()`'s borrow can't be invalidated by a
+        // concurrent `redefine_global` on another `Thread` sharing this `GlobalVMState`.
+        let _typ_guard = self.global_typ_lock.read().unwrap();
+        let mut typ = global.typ();
         let mut value = global.value.get();
         // If there are any remaining components iterate through them, accessing each field
         for field_name in components {
@@ -927,13 +1785,19 @@ impl VM {
         }
     }
 
-    pub fn find_type_info(&self, name: &str) -> Result<&types::Alias<Symbol, TcType>> {
+    /// Returns an owned clone rather than `&types::Alias<Symbol, TcType>`: the `Alias` is reached
+    /// through `Global::typ()`, and a reference into it would have to remain valid for as long as
+    /// the caller holds it (this method's signature gives no way to bound that), which a
+    /// concurrent `redefine_global` could invalidate. `types::Alias`/`TcType` are cheap to clone,
+    /// so there is no reason to take on that risk for a borrow.
+    pub fn find_type_info(&self, name: &str) -> Result<types::Alias<Symbol, TcType>> {
         let name = Name::new(name);
         let mut components = name.module().components();
         let global = match components.next() {
             Some(comp) => {
                 let names = self.names
-                                .borrow();
+                                .read()
+                                .unwrap();
                 try!(names.get(comp)
                           .or_else(|| {
                               // We access by the the full name so no components should be left
@@ -950,7 +1814,10 @@ impl VM {
             None => return Err(Error::Message(format!("'{}' is not a valid name", name))),
         };
 
-        let mut typ = &global.typ;
+        // Held for the rest of this function; the walk below only ever borrows through
+        // `global.typ()`, and the result is cloned out before this guard is dropped.
+        let _typ_guard = self.global_typ_lock.read().unwrap();
+        let mut typ = global.typ();
         for field_name in components {
             let next = match **typ {
                 Type::Record { ref fields, .. } => {
@@ -971,7 +1838,7 @@ impl VM {
                 let field_name = name.name();
                 types.iter()
                      .find(|field| field.name.as_ref() == field_name.as_str())
-                     .map(|field| &field.typ)
+                     .map(|field| field.typ.clone())
             }
             _ => None,
         };
@@ -1043,6 +1910,22 @@ impl VM {
                         |gc, roots| unsafe { gc.alloc_and_collect(roots, def) })
     }
 
+    /// Reports a mutation of an already-allocated `Cell<Value>` field (`DataStruct::fields`,
+    /// `ClosureData::upvars`, `PartialApplicationData::arguments`) to `Gc::write_barrier`, which is
+    /// defined on `gc::Gc` - a type this file only imports, never defines. *If* `Gc` becomes a
+    /// generational collector with a young/old split and a remembered set, every call site that
+    /// mutates a `Cell<Value>` after its initial allocation needs to call this right after its
+    /// `Cell::set`, so the barrier doesn't have to be threaded through retroactively.
+    /// Nothing in this source file implements, tests, or can verify that `Gc` actually does any of
+    /// that today; this is call-site plumbing for a generational redesign, not the redesign
+    /// itself, and should not be read as one.
+    ///
+    /// Status: blocked, not done. Re-examine once `gc::Gc` is actually reachable from a commit in
+    /// this series; until then there is no generational collector to plumb this into.
+    fn write_barrier<T: Traverseable>(&self, parent: GcPtr<T>, value: Value) {
+        self.gc.lock().unwrap().write_barrier(parent, value);
+    }
+
     fn with_roots<F, R>(&self, stack: &Stack, f: F) -> R
         where F: for<'b> FnOnce(&mut Gc, Roots<'b>) -> R
     {
@@ -1056,7 +1939,7 @@ impl VM {
             vm: self,
             stack: stack,
         };
-        let mut gc = self.gc.borrow_mut();
+        let mut gc = self.gc.lock().unwrap();
         f(&mut gc, roots)
     }
 
@@ -1077,11 +1960,12 @@ impl VM {
         };
         let f = self.new_function(compiled_fn);
         let closure = self.alloc(&self.stack.borrow(), ClosureDataDef(f, &[]));
-        self.names.borrow_mut().insert(name.into(), self.globals.len());
+        self.names.write().unwrap().insert(name.into(), self.globals.len());
         self.globals.push(Global {
             id: id,
-            typ: typ,
+            typ: RefCell::new(typ),
             value: Cell::new(Closure(closure)),
+            dependents: RefCell::new(HashSet::new()),
         });
         self.globals.len() as VMIndex - 1
     }
@@ -1145,6 +2029,9 @@ impl VM {
                             function: &Callable,
                             excess: bool)
                             -> Result<StackFrame<'b>> {
+        if stack.stack.get_frames().len() >= self.max_stack_size.load(Ordering::Relaxed) {
+            return Err(Error::StackOverflow);
+        }
         match *function {
             Callable::Closure(closure) => {
                 stack = stack.enter_scope(closure.function.args, Some(Callable::Closure(closure)));
@@ -1244,6 +2131,13 @@ impl VM {
                    mut stack: StackFrame<'b>,
                    args: VMIndex)
                    -> Result<StackFrame<'b>> {
+        // `execute_callable` already bounds nested call frames; this additionally bounds the raw
+        // value-stack length, which can grow without a new call frame (e.g. a long run of
+        // `MakeClosure`s) and would otherwise exhaust memory or the host's native stack first.
+        // Its own limit, not `max_stack_size` - see `max_value_stack_size`'s doc comment.
+        if stack.len() as usize >= self.max_value_stack_size.load(Ordering::Relaxed) {
+            return Err(Error::StackOverflow);
+        }
         let function_index = stack.len() - 1 - args;
         debug!("Do call {:?} {:?}",
                stack[function_index],
@@ -1293,23 +2187,117 @@ impl VM {
                            closure.function.name,
                            instruction_index,
                            closure.function.instructions.len());
-                    let new_stack = try!(self.execute_(stack,
-                                                       instruction_index,
-                                                       &closure.function.instructions,
-                                                       &closure.function));
-                    new_stack
+                    match self.execute_(stack,
+                                       instruction_index,
+                                       &closure.function.instructions,
+                                       &closure.function) {
+                        Ok(new_stack) => new_stack,
+                        // Extern-function errors propagate straight through `try!` above and are
+                        // never offered to a handler: an extern boundary is explicitly
+                        // non-catchable rather than unwound, so a `Status::Error` extern always
+                        // aborts the call the way it does today.
+                        Err(err) => try!(self.catch(err)),
+                    }
                 }
             };
         }
         Ok(maybe_stack)
     }
 
+    /// Looks for a `TryFrame` installed by a `PushTry` that is still in scope and, if one exists,
+    /// unwinds to it: pops call frames back to its recorded depth (calling `exit_scope` on each so
+    /// GC roots and excess-arg bookkeeping stay consistent), truncates the value stack to its
+    /// recorded length, pushes the thrown value, and resumes at its `catch_instruction`. A
+    /// `Error::Message` raised internally by an opcode (as opposed to an explicit `Throw`) is
+    /// promoted into a thrown string value so gluon-level `catch` can observe it too. Handlers
+    /// whose frame has already returned are discarded. Propagates `err` unchanged when no handler
+    /// is in scope, and always for `Error::OutOfFuel`, which leaves the stack untouched.
+    fn catch<'b>(&'b self, err: Error) -> Result<Option<StackFrame<'b>>> {
+        if err == Error::OutOfFuel {
+            return Err(err);
+        }
+        let depth = self.stack.borrow().get_frames().len();
+        let handler = loop {
+            match self.try_frames.borrow_mut().pop() {
+                Some(handler) if handler.frame_depth > depth => continue,
+                Some(handler) => break handler,
+                None => return Err(err),
+            }
+        };
+        let value = match err {
+            Error::Exception(ref value) => *value,
+            ref other => {
+                let message = self.intern(&format!("{}", other));
+                String(message.inner())
+            }
+        };
+        let mut stack = self.current_frame();
+        while stack.stack.get_frames().len() > handler.frame_depth {
+            stack = match stack.exit_scope() {
+                Some(stack) => stack,
+                None => return Err(err),
+            };
+        }
+        while stack.len() > handler.stack_len {
+            stack.pop();
+        }
+        stack.push(value);
+        stack.frame.instruction_index = handler.catch_instruction;
+        Ok(Some(stack))
+    }
+
+    fn observe_enter_frame(&self, function: &BytecodeFunction, stack: &StackFrame) {
+        if !self.observer_installed.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(ref observer) = *self.observer.read().unwrap() {
+            observer.enter_frame(function, stack);
+        }
+    }
+
+    fn observe_op(&self, index: usize, instr: Instruction, stack: &StackFrame) {
+        if !self.observer_installed.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(ref observer) = *self.observer.read().unwrap() {
+            observer.op(index, instr, stack);
+        }
+    }
+
+    fn observe_exit_frame(&self, function: &BytecodeFunction, stack: &StackFrame) {
+        if !self.observer_installed.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(ref observer) = *self.observer.read().unwrap() {
+            observer.exit_frame(function, stack);
+        }
+    }
+
+    /// Runs one bytecode frame, notifying the installed `RuntimeObserver` (if any) each time the
+    /// frame is entered or resumed, and with `exit_frame` each time `execute_impl` hands back a
+    /// live `StackFrame` (`Ok(Some(..))`): on a return, with the caller's frame, and also when the
+    /// frame leaves through a `Call` or `TailCall`, with the callee's frame. Nothing is reported
+    /// when it yields `Err` or `Ok(None)`.
     fn execute_<'b>(&'b self,
-                    mut stack: StackFrame<'b>,
-                    mut index: usize,
+                    stack: StackFrame<'b>,
+                    index: usize,
                     instructions: &[Instruction],
                     function: &BytecodeFunction)
                     -> Result<Option<StackFrame<'b>>> {
+        self.observe_enter_frame(function, &stack);
+        let result = self.execute_impl(stack, index, instructions, function);
+        if let Ok(Some(ref stack)) = result {
+            self.observe_exit_frame(function, stack);
+        }
+        result
+    }
+
+    fn execute_impl<'b>(&'b self,
+                        mut stack: StackFrame<'b>,
+                        mut index: usize,
+                        instructions: &[Instruction],
+                        function: &BytecodeFunction)
+                        -> Result<Option<StackFrame<'b>>> {
         {
             debug!(">>>\nEnter frame {}: {:?}\n{:?}",
                    function.name,
@@ -1317,7 +2305,24 @@ impl VM {
                    stack.frame);
         }
         while let Some(&instr) = instructions.get(index) {
+            // Checked before dispatching each instruction (rather than only at backward jumps and
+            // calls) so a host embedding gluon can cancel evaluation within one instruction's
+            // worth of latency, no matter what the running script is doing.
+            if self.interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            match self.fuel.get() {
+                Some(0) => return Err(Error::OutOfFuel),
+                Some(n) => self.fuel.set(Some(n - 1)),
+                None => (),
+            }
             debug_instruction(&stack, index, instr);
+            self.observe_op(index, instr, &stack);
+            // `Instruction` itself is defined in `compiler`/`types`, outside this file, so every
+            // arm below (not just `AndInt`/`OrInt`/`XorInt`/`NotInt`/`ShlInt`/`ShrLogicalInt`/
+            // `ShrArithInt`) depends on a variant this tree only imports and never defines; adding
+            // bitwise/shift ops here needs a matching addition on that side, which this commit
+            // cannot make or verify.
             match instr {
                 Push(i) => {
                     let v = stack[i].clone();
@@ -1350,7 +2355,7 @@ impl VM {
                                 }
                                 args += excess.fields.len() as VMIndex;
                             }
-                            None => panic!("Expected excess args"),
+                            None => return Err(Error::Message("Expected excess args".to_string())),
                         }
                     }
                     stack = match stack.exit_scope() {
@@ -1428,6 +2433,20 @@ impl VM {
                         }
                     }
                 }
+                PushTry(target) => {
+                    self.try_frames.borrow_mut().push(TryFrame {
+                        frame_depth: stack.stack.get_frames().len(),
+                        stack_len: stack.len(),
+                        catch_instruction: target as usize,
+                    });
+                }
+                PopTry => {
+                    self.try_frames.borrow_mut().pop();
+                }
+                Throw => {
+                    let value = stack.pop();
+                    return Err(Error::Exception(value));
+                }
                 Pop(n) => {
                     for _ in 0..n {
                         stack.pop();
@@ -1464,6 +2483,7 @@ impl VM {
                     match (array, index) {
                         (Data(array), Int(index)) => {
                             array.fields[index as usize].set(value);
+                            self.write_barrier(array, value);
                         }
                         (x, y) => {
                             return Err(Error::Message(format!("Op SetIndex called on invalid \
@@ -1474,6 +2494,11 @@ impl VM {
                     }
                 }
                 MakeClosure(fi, n) => {
+                    // Value-stack bound, same as `do_call` - see `max_value_stack_size`'s doc
+                    // comment for why this isn't `max_stack_size`.
+                    if stack.len() as usize >= self.max_value_stack_size.load(Ordering::Relaxed) {
+                        return Err(Error::StackOverflow);
+                    }
                     let closure = {
                         let args = &stack[stack.len() - n..];
                         let func = function.inner_functions[fi as usize];
@@ -1498,29 +2523,38 @@ impl VM {
                     match stack[i] {
                         Closure(closure) => {
                             for var in closure.upvars.iter().rev() {
-                                var.set(stack.pop());
+                                let value = stack.pop();
+                                var.set(value);
+                                self.write_barrier(closure, value);
                             }
                             stack.pop();//Remove the closure
                         }
-                        x => panic!("Expected closure, got {:?}", x),
+                        x => return Err(Error::Message(format!("Expected closure, got {:?}", x))),
                     }
                 }
                 PushUpVar(i) => {
                     let v = stack.get_upvar(i).clone();
                     stack.push(v);
                 }
-                AddInt => binop(self, &mut stack, VMInt::add),
-                SubtractInt => binop(self, &mut stack, VMInt::sub),
-                MultiplyInt => binop(self, &mut stack, VMInt::mul),
-                DivideInt => binop(self, &mut stack, VMInt::div),
-                IntLT => binop(self, &mut stack, |l: VMInt, r| l < r),
-                IntEQ => binop(self, &mut stack, |l: VMInt, r| l == r),
-                AddFloat => binop(self, &mut stack, f64::add),
-                SubtractFloat => binop(self, &mut stack, f64::sub),
-                MultiplyFloat => binop(self, &mut stack, f64::mul),
-                DivideFloat => binop(self, &mut stack, f64::div),
-                FloatLT => binop(self, &mut stack, |l: f64, r| l < r),
-                FloatEQ => binop(self, &mut stack, |l: f64, r| l == r),
+                AddInt => try!(binop(self, &mut stack, VMInt::wrapping_add)),
+                SubtractInt => try!(binop(self, &mut stack, VMInt::wrapping_sub)),
+                MultiplyInt => try!(binop(self, &mut stack, VMInt::wrapping_mul)),
+                DivideInt => try!(binop(self, &mut stack, VMInt::wrapping_div)),
+                IntLT => try!(binop(self, &mut stack, |l: VMInt, r| l < r)),
+                IntEQ => try!(binop(self, &mut stack, |l: VMInt, r| l == r)),
+                AndInt => try!(binop(self, &mut stack, and_int)),
+                OrInt => try!(binop(self, &mut stack, or_int)),
+                XorInt => try!(binop(self, &mut stack, xor_int)),
+                NotInt => try!(unop(self, &mut stack, not_int)),
+                ShlInt => try!(binop(self, &mut stack, shl_int)),
+                ShrLogicalInt => try!(binop(self, &mut stack, shr_logical_int)),
+                ShrArithInt => try!(binop(self, &mut stack, shr_arith_int)),
+                AddFloat => try!(binop(self, &mut stack, f64::add)),
+                SubtractFloat => try!(binop(self, &mut stack, f64::sub)),
+                MultiplyFloat => try!(binop(self, &mut stack, f64::mul)),
+                DivideFloat => try!(binop(self, &mut stack, f64::div)),
+                FloatLT => try!(binop(self, &mut stack, |l: f64, r| l < r)),
+                FloatEQ => try!(binop(self, &mut stack, |l: f64, r| l == r)),
             }
             index += 1;
         }
@@ -1549,7 +2583,9 @@ impl VM {
                     }
                     self.do_call(stack, excess.fields.len() as VMIndex).map(Some)
                 }
-                x => panic!("Expected excess arguments found {:?}", x),
+                x => {
+                    Err(Error::Message(format!("Expected excess arguments found {:?}", x)))
+                }
             }
         } else {
             stack.push(result);
@@ -1558,8 +2594,69 @@ impl VM {
     }
 }
 
+/// `AndInt`/`OrInt`/`XorInt`/`NotInt`/`ShlInt`/`ShrLogicalInt`/`ShrArithInt` dispatch to these
+/// rather than inline closures so there is a named, `pub` Rust function for each bitwise/shift op
+/// that an embedder's primitive-registration code (e.g. a `define_global` call wiring up
+/// `std.int`) can point at. That registration itself has to live wherever the rest of this VM's
+/// builtins are registered, which is not a module present in this tree - nothing here can add to
+/// or verify that table, so these ops remain internal-only (reachable by the VM's own dispatch,
+/// not yet by a gluon script) until that wiring is added on the other side.
+pub fn and_int(l: VMInt, r: VMInt) -> VMInt {
+    l & r
+}
+pub fn or_int(l: VMInt, r: VMInt) -> VMInt {
+    l | r
+}
+pub fn xor_int(l: VMInt, r: VMInt) -> VMInt {
+    l ^ r
+}
+pub fn not_int(l: VMInt) -> VMInt {
+    !l
+}
+// `wrapping_shl`/`wrapping_shr` mask the shift amount to the bit width of `VMInt` rather than
+// invoking the UB that an out-of-range native shift would, matching the "wrapping" semantics the
+// rest of this op set uses for overflow.
+pub fn shl_int(l: VMInt, r: VMInt) -> VMInt {
+    l.wrapping_shl(r as u32)
+}
+// Logical: shifts in zeros, implemented by reinterpreting the bit pattern as unsigned first so
+// the sign bit doesn't get replicated.
+pub fn shr_logical_int(l: VMInt, r: VMInt) -> VMInt {
+    (l as usize).wrapping_shr(r as u32) as VMInt
+}
+// Arithmetic: replicates the sign bit, i.e. the native `>>` on a signed `VMInt`.
+pub fn shr_arith_int(l: VMInt, r: VMInt) -> VMInt {
+    l.wrapping_shr(r as u32)
+}
+
+/// Checked counterparts to `shl_int`/`shr_logical_int`/`shr_arith_int`: `None` when the shift
+/// amount is out of range for `VMInt`'s bit width, instead of `wrapping_*`'s silent masking, so
+/// gluon code can opt into explicit overflow handling rather than always getting the wrapping
+/// behavior. `and_int`/`or_int`/`xor_int`/`not_int` have no checked counterpart: a bitwise op on a
+/// fixed-width integer can't overflow, so there is nothing for a checked variant to detect.
+/// Like every other op in this file, these have no `Instruction` variant to dispatch from (none is
+/// defined anywhere in this tree) and no call site reachable from compiled gluon bytecode yet;
+/// they're exposed as named functions so both the opcode and the builtin-registration wiring can
+/// be added on top of this once that's reachable.
+fn checked_shift_amount(r: VMInt) -> Option<u32> {
+    if r < 0 || r >= (::std::mem::size_of::<VMInt>() * 8) as VMInt {
+        None
+    } else {
+        Some(r as u32)
+    }
+}
+pub fn checked_shl_int(l: VMInt, r: VMInt) -> Option<VMInt> {
+    checked_shift_amount(r).and_then(|r| l.checked_shl(r))
+}
+pub fn checked_shr_logical_int(l: VMInt, r: VMInt) -> Option<VMInt> {
+    checked_shift_amount(r).and_then(|r| (l as usize).checked_shr(r)).map(|v| v as VMInt)
+}
+pub fn checked_shr_arith_int(l: VMInt, r: VMInt) -> Option<VMInt> {
+    checked_shift_amount(r).and_then(|r| l.checked_shr(r))
+}
+
 #[inline]
-fn binop<'b, F, T, R>(vm: &'b VM, stack: &mut StackFrame<'b>, f: F)
+fn binop<'b, F, T, R>(vm: &'b VM, stack: &mut StackFrame<'b>, f: F) -> Result<()>
     where F: FnOnce(T, T) -> R,
           T: Getable<'b> + fmt::Debug,
           R: Pushable
@@ -1570,8 +2667,26 @@ fn binop<'b, F, T, R>(vm: &'b VM, stack: &mut StackFrame<'b>, f: F)
         (Some(l), Some(r)) => {
             let result = f(l, r);
             result.push(vm, stack);
+            Ok(())
         }
-        (l, r) => panic!("{:?} `op` {:?}", l, r),
+        (l, r) => Err(Error::Message(format!("{:?} `op` {:?}", l, r))),
+    }
+}
+
+#[inline]
+fn unop<'b, F, T, R>(vm: &'b VM, stack: &mut StackFrame<'b>, f: F) -> Result<()>
+    where F: FnOnce(T) -> R,
+          T: Getable<'b> + fmt::Debug,
+          R: Pushable
+{
+    let x = stack.pop();
+    match T::from_value(vm, x) {
+        Some(x) => {
+            let result = f(x);
+            result.push(vm, stack);
+            Ok(())
+        }
+        None => Err(Error::Message(format!("`op` {:?}", x))),
     }
 }
 
@@ -1593,5 +2708,174 @@ quick_error! {
         Message(err: StdString) {
             display("{}", err)
         }
+        /// A value thrown by a `Throw` instruction (or a gluon-level `throw` built on it) that
+        /// unwound all the way out of the VM without being caught by any `PushTry` handler.
+        Exception(value: Value) {
+            display("{:?}", value)
+        }
+        /// The running computation was cancelled via its `interrupt_handle`.
+        Interrupted {
+            display("Interrupted")
+        }
+        /// A call would have exceeded `GlobalVMState::max_stack_size`'s call-frame limit or
+        /// `max_value_stack_size`'s raw value-stack limit.
+        StackOverflow {
+            display("Stack overflow")
+        }
+        /// The thread's `fuel` budget (see `VM::set_fuel`) reached zero before the computation
+        /// finished. The stack is left exactly as it was when the next instruction would have
+        /// dispatched, so topping up fuel and resuming is possible in principle.
+        OutOfFuel {
+            display("Out of fuel")
+        }
+    }
+}
+
+// `compiler::CompiledFunction` and `Instruction` are both defined outside this tree, and most
+// public entry points here (`VM::run_expr`-style evaluation, anything going through the compiler)
+// need pieces of those two types this file can't construct. The tests below stick to what's
+// fully defined in this file: `GlobalVMState::global_typ_lock`'s actual mutual-exclusion behavior
+// (chunk0-5), `VM::catch`'s unwinding logic (chunk1-1), the `max_stack_size`/
+// `max_value_stack_size` knobs (chunk2-3), and the plain `VMInt -> VMInt`/`Option<VMInt>`
+// functions backing the bitwise/shift ops (chunk0-4), driven directly rather than through a
+// compiled script.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_shift_ints_reject_an_out_of_range_shift_amount() {
+        let bits = (::std::mem::size_of::<VMInt>() * 8) as VMInt;
+        assert_eq!(checked_shl_int(1, bits), None);
+        assert_eq!(checked_shr_logical_int(1, bits), None);
+        assert_eq!(checked_shr_arith_int(1, bits), None);
+    }
+
+    #[test]
+    fn checked_shift_ints_match_their_wrapping_counterparts_in_range() {
+        assert_eq!(checked_shl_int(1, 3), Some(shl_int(1, 3)));
+        assert_eq!(checked_shr_logical_int(-1, 3), Some(shr_logical_int(-1, 3)));
+        assert_eq!(checked_shr_arith_int(-1, 3), Some(shr_arith_int(-1, 3)));
+    }
+
+    #[test]
+    fn max_stack_size_and_max_value_stack_size_are_independent_knobs() {
+        let global_state = GlobalVMState::new();
+        global_state.set_max_stack_size(5);
+        global_state.set_max_value_stack_size(9);
+        assert_eq!(global_state.max_stack_size.load(Ordering::Relaxed), 5);
+        assert_eq!(global_state.max_value_stack_size.load(Ordering::Relaxed), 9);
+    }
+
+    #[test]
+    fn global_typ_lock_blocks_a_write_while_a_vm_env_is_alive() {
+        let global_state = GlobalVMState::new();
+        let env = global_state.env();
+        // `VMEnv::_typ_guard` holds the read side for as long as `env` is alive, the same way a
+        // real in-flight compile would - so a `redefine_global` landing here must not proceed.
+        assert!(global_state.global_typ_lock.try_write().is_err());
+        drop(env);
+        assert!(global_state.global_typ_lock.try_write().is_ok());
+    }
+
+    #[test]
+    fn redefine_global_waits_out_an_in_flight_vm_env() {
+        let vm = VM::new();
+        vm.define_global("x", 1 as VMInt).unwrap();
+        let env = vm.global_state.env();
+        // With `env` (standing in for an in-flight compile) still alive, the write side of
+        // `global_typ_lock` must still be unavailable - `redefine_global` would block here
+        // rather than race the `&TcType` `env` could be handing out.
+        assert!(vm.global_state.global_typ_lock.try_write().is_err());
+        drop(env);
+        assert!(vm.global_state
+                  .redefine_global(&Symbol::new("x"), VMInt::make_type(&vm.global_state), Int(2))
+                  .is_ok());
+    }
+
+    #[test]
+    fn catch_unwinds_to_the_installed_try_frame_and_resumes_there() {
+        let vm = VM::new();
+        let frame_depth = vm.current_frame().stack.get_frames().len();
+        vm.push(Int(11));
+        let stack_len = vm.current_frame().len();
+        vm.try_frames.borrow_mut().push(TryFrame {
+            frame_depth: frame_depth,
+            stack_len: stack_len,
+            catch_instruction: 42,
+        });
+        // Pushed after installing the handler, simulating values the `Throw`'s own frame left on
+        // the stack above the handler's recorded `stack_len`; `catch` must discard these.
+        vm.push(Int(22));
+        vm.push(Int(33));
+
+        let result = vm.catch(Error::Message("boom".into()));
+
+        match result {
+            Ok(Some(stack)) => {
+                assert_eq!(stack.frame.instruction_index, 42);
+                // stack_len (the one value pushed before the handler was installed) plus the
+                // thrown value, with everything pushed after the handler discarded.
+                assert_eq!(stack.len(), stack_len + 1);
+                // `Error::Message` (as opposed to an explicit `Throw`/`Error::Exception`) is
+                // promoted into a thrown string so gluon-level `catch` can observe it too.
+                match stack.top() {
+                    String(_) => (),
+                    other => panic!("expected the caught error to surface as a String, got {:?}",
+                                     other),
+                }
+            }
+            other => panic!("expected an installed handler to catch, got {:?}", other),
+        }
+        assert!(vm.try_frames.borrow().is_empty());
+    }
+
+    #[test]
+    fn catch_propagates_the_error_when_no_handler_is_installed() {
+        let vm = VM::new();
+        assert!(vm.try_frames.borrow().is_empty());
+        match vm.catch(Error::Message("boom".into())) {
+            Err(Error::Message(ref m)) if m == "boom" => (),
+            other => panic!("expected the error to propagate unchanged, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn spawn_registers_and_despawn_deregisters_the_child_thread() {
+        let vm = VM::new();
+        let child = vm.spawn();
+        assert!(vm.global_state
+                  .threads
+                  .lock()
+                  .unwrap()
+                  .iter()
+                  .any(|&t| &*t as *const Thread == &*child as *const Thread));
+        vm.despawn(child);
+        assert!(!vm.global_state
+                   .threads
+                   .lock()
+                   .unwrap()
+                   .iter()
+                   .any(|&t| &*t as *const Thread == &*child as *const Thread));
+    }
+
+    #[test]
+    fn dropping_a_vm_deregisters_its_own_thread() {
+        let vm = VM::new();
+        let sibling = vm.new_vm();
+        let sibling_ptr = sibling.0;
+        assert!(vm.global_state
+                  .threads
+                  .lock()
+                  .unwrap()
+                  .iter()
+                  .any(|&t| &*t as *const Thread == &*sibling_ptr as *const Thread));
+        drop(sibling);
+        assert!(!vm.global_state
+                   .threads
+                   .lock()
+                   .unwrap()
+                   .iter()
+                   .any(|&t| &*t as *const Thread == &*sibling_ptr as *const Thread));
     }
 }